@@ -1,63 +1,295 @@
-use std::ops::Div;
-use std::option;
+use std::ops::{Add, Div, Mul, Sub};
 
-pub fn inlined_func(array: &[f32], scaling: f32) -> f32 {
-    array
-        .iter()
-        .map(|x| *x * scaling)
-        .map(|x| x + 1.98765432)
-        .fold(0_f32, |sum, x| sum + x)
-        .div(array.len() as f32)
+/// Minimal numeric abstraction so the kernels below can operate over both
+/// `f32` and `f64` without duplicating any logic. Deliberately small: it
+/// only covers the operations `scale`/`offset`/`mean` and friends actually
+/// need, rather than pulling in a full numeric-traits dependency.
+pub trait Float:
+    Copy + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self>
+{
+    fn zero() -> Self;
+    fn from_usize(n: usize) -> Self;
+    /// Lets callers express literal constants (like `1.98765432`) generically.
+    fn from_f64(v: f64) -> Self;
 }
 
-pub fn scale(array: &mut [f32], scaling: f32) {
+impl Float for f32 {
+    fn zero() -> Self {
+        0.0
+    }
+    fn from_usize(n: usize) -> Self {
+        n as f32
+    }
+    fn from_f64(v: f64) -> Self {
+        v as f32
+    }
+}
+
+impl Float for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+    fn from_usize(n: usize) -> Self {
+        n as f64
+    }
+    fn from_f64(v: f64) -> Self {
+        v
+    }
+}
+
+/// Sums `array` using Kahan compensated summation, which tracks the
+/// rounding error lost on each addition and feeds it back in on the next
+/// one. This keeps long sums of values of differing magnitude much closer
+/// to their higher-precision reference than a plain `fold` would.
+pub fn kahan_sum<T: Float>(array: &[T]) -> T {
+    let mut sum = T::zero();
+    let mut c = T::zero();
+    for &x in array {
+        let y = x - c;
+        let t = sum + y;
+        c = (t - sum) - y;
+        sum = t;
+    }
+    sum
+}
+
+pub fn scale<T: Float>(array: &mut [T], scaling: T) {
     array.iter_mut().for_each(|x| {
         *x = *x * scaling;
     })
 }
 
-pub fn offset(array: &mut [f32], y: f32) {
+pub fn offset<T: Float>(array: &mut [T], y: T) {
     array.iter_mut().for_each(|x| {
         *x = *x + y;
     })
 }
 
-pub fn mean(array: &[f32]) -> f32 {
-    let sum = array.iter().fold(0_f32, |sum, x| sum + x);
-    sum / array.len() as f32
+/// Non-mutating counterpart to `scale`: returns a new `Vec` instead of
+/// requiring a mutable borrow of `array`, for callers that want to keep
+/// their original data around.
+pub fn scaled<T: Float>(array: &[T], scaling: T) -> Vec<T> {
+    array.iter().map(|x| *x * scaling).collect()
+}
+
+/// Non-mutating counterpart to `offset`: returns a new `Vec` instead of
+/// requiring a mutable borrow of `array`.
+pub fn offset_by<T: Float>(array: &[T], y: T) -> Vec<T> {
+    array.iter().map(|x| *x + y).collect()
 }
 
-pub fn composed_func(array: &mut [f32], scaling: f32) -> f32 {
-    scale(array, scaling);
-    offset(array, 1.98765432f32);
-    mean(array)
+/// Applies `scaled` then `offset_by` in one pass, without materializing the
+/// intermediate scaled `Vec`.
+pub fn transform<T: Float>(array: &[T], scaling: T, y: T) -> Vec<T> {
+    array.iter().map(|x| *x * scaling + y).collect()
 }
 
-pub struct Y {
- x: i32,
- y: i32
+pub fn mean<T: Float>(array: &[T]) -> T {
+    kahan_sum(array) / T::from_usize(array.len())
 }
 
-pub fn main() {
-    let ff :Option<Y> = None;
-    ff.is_some();
+/// Computes the mean and population variance of `array` in a single pass
+/// using Welford's online algorithm, which is far more numerically stable
+/// than summing values (or their squares) directly.
+///
+/// Returns `(0.0, 0.0)` for an empty slice rather than dividing by zero.
+pub fn stats(array: &[f32]) -> (f32, f32) {
+    if array.is_empty() {
+        return (0.0, 0.0);
+    }
+
+    let mut n: u32 = 0;
+    let mut m = 0_f32;
+    let mut m2 = 0_f32;
+    for &x in array {
+        n += 1;
+        let d = x - m;
+        m += d / n as f32;
+        m2 += d * (x - m);
+    }
+    (m, m2 / n as f32)
+}
+
+/// A builder for chaining element-wise ops (`scale`, `offset`, `map`) over a
+/// slice and terminating with a reducer (`mean`, `sum`, `fold`). Each step
+/// composes its closure into the running one rather than writing back to an
+/// intermediate buffer, so the whole chain compiles down to a single fused
+/// pass over `array`.
+pub struct Pipeline<'a, T: Float, F: Fn(T) -> T> {
+    array: &'a [T],
+    op: F,
+}
+
+impl<'a, T: Float> Pipeline<'a, T, fn(T) -> T> {
+    pub fn new(array: &'a [T]) -> Self {
+        fn identity<T>(x: T) -> T {
+            x
+        }
+        Pipeline { array, op: identity }
+    }
+}
+
+impl<'a, T: Float, F: Fn(T) -> T> Pipeline<'a, T, F> {
+    pub fn scale(self, k: T) -> Pipeline<'a, T, impl Fn(T) -> T> {
+        let op = self.op;
+        Pipeline {
+            array: self.array,
+            op: move |x| op(x) * k,
+        }
+    }
+
+    pub fn offset(self, y: T) -> Pipeline<'a, T, impl Fn(T) -> T> {
+        let op = self.op;
+        Pipeline {
+            array: self.array,
+            op: move |x| op(x) + y,
+        }
+    }
+
+    pub fn map<G: Fn(T) -> T>(self, f: G) -> Pipeline<'a, T, impl Fn(T) -> T> {
+        let op = self.op;
+        Pipeline {
+            array: self.array,
+            op: move |x| f(op(x)),
+        }
+    }
+
+    pub fn sum(self) -> T {
+        let op = self.op;
+        let mut sum = T::zero();
+        let mut c = T::zero();
+        for x in self.array.iter().map(|x| op(*x)) {
+            let y = x - c;
+            let t = sum + y;
+            c = (t - sum) - y;
+            sum = t;
+        }
+        sum
+    }
+
+    pub fn mean(self) -> T {
+        let len = T::from_usize(self.array.len());
+        self.sum().div(len)
+    }
+
+    pub fn fold(self, init: T, f: impl Fn(T, T) -> T) -> T {
+        let op = self.op;
+        self.array.iter().map(|x| op(*x)).fold(init, f)
+    }
+}
+
+/// SIMD fast path for the `f32` element-wise kernels, gated behind the
+/// `simd` feature. Processes the slice in `f32x8` lanes via the `wide`
+/// crate (stable, unlike `std::simd`) and falls back to the scalar
+/// implementation for the remainder tail; results match the scalar path
+/// to within float rounding tolerance.
+#[cfg(feature = "simd")]
+mod simd_ops {
+    use wide::f32x8;
+
+    pub fn scale(array: &mut [f32], scaling: f32) {
+        let lanes = f32x8::splat(scaling);
+        let mut chunks = array.chunks_exact_mut(8);
+        for chunk in &mut chunks {
+            let v: [f32; 8] = chunk.try_into().unwrap();
+            chunk.copy_from_slice(&(f32x8::from(v) * lanes).to_array());
+        }
+        super::scale(chunks.into_remainder(), scaling);
+    }
+
+    pub fn offset(array: &mut [f32], y: f32) {
+        let lanes = f32x8::splat(y);
+        let mut chunks = array.chunks_exact_mut(8);
+        for chunk in &mut chunks {
+            let v: [f32; 8] = chunk.try_into().unwrap();
+            chunk.copy_from_slice(&(f32x8::from(v) + lanes).to_array());
+        }
+        super::offset(chunks.into_remainder(), y);
+    }
+}
+
+#[cfg(feature = "simd")]
+pub use simd_ops::{offset as offset_simd, scale as scale_simd};
+
+/// Parallel `mean`, gated behind the `parallel` feature: splits `array`
+/// across rayon's thread pool, reduces each chunk with `kahan_sum`, then
+/// combines the per-thread partials with one final Kahan pass so the
+/// result stays as accurate as the single-threaded version.
+#[cfg(feature = "parallel")]
+pub fn mean_parallel(array: &[f32]) -> f32 {
+    use rayon::prelude::*;
+
+    if array.is_empty() {
+        return 0.0;
+    }
+
+    let chunk_len = (array.len() / rayon::current_num_threads()).max(1);
+    let partials: Vec<f32> = array
+        .par_chunks(chunk_len)
+        .map(kahan_sum)
+        .collect();
+    kahan_sum(&partials) / array.len() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_matches_known_mean_and_variance() {
+        let data = [2.0_f32, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let (mean, variance) = stats(&data);
+        assert!((mean - 5.0).abs() < 1e-5);
+        assert!((variance - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn stats_of_empty_slice_is_zero() {
+        assert_eq!(stats(&[]), (0.0, 0.0));
+    }
+
+    #[test]
+    fn kahan_sum_is_more_accurate_than_naive_fold() {
+        let mut data = vec![1.0e8_f32];
+        data.extend(std::iter::repeat_n(1.0_f32, 10));
+
+        let reference: f64 = data.iter().map(|&x| x as f64).sum();
+        let naive = data.iter().fold(0.0_f32, |sum, x| sum + x);
+        let kahan = kahan_sum(&data);
+
+        let naive_err = (naive as f64 - reference).abs();
+        let kahan_err = (kahan as f64 - reference).abs();
+        assert!(kahan_err < naive_err);
+    }
+
+    #[cfg(feature = "simd")]
+    #[test]
+    fn scale_simd_matches_scalar_scale() {
+        let mut scalar = (0..20).map(|i| i as f32 * 0.37).collect::<Vec<_>>();
+        let mut simd = scalar.clone();
+        scale(&mut scalar, 1.5);
+        scale_simd(&mut simd, 1.5);
+        for (a, b) in scalar.iter().zip(simd.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
 
-    let g:Result<i32,i32> = OK(9);
+    #[cfg(feature = "simd")]
+    #[test]
+    fn offset_simd_matches_scalar_offset() {
+        let mut scalar = (0..20).map(|i| i as f32 * 0.37).collect::<Vec<_>>();
+        let mut simd = scalar.clone();
+        offset(&mut scalar, 1.987_654_3);
+        offset_simd(&mut simd, 1.987_654_3);
+        for (a, b) in scalar.iter().zip(simd.iter()) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
 
-    g.is_err()
-    let _c = Y{x:65,y:89};
-    match ff {
-        Some(_c) => {println!("{}",9);} ,
-        Some(_c) => {println!("{}>>", 9);},
-        Some(_c) => {println!{"{} ", 9}; },
-        None => {}
-    };
-    let mut arr_a = [3.4, 5.7, 9.0];
-    let arr_b = [3.4, 5.7, 9.0];
-    println!(
-        "{} {}",
-        composed_func(&mut arr_a, 0.6),
-        inlined_func(&arr_b, 0.6)
-    )
-    option::exp
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn mean_parallel_matches_scalar_mean() {
+        let data = (0..1000).map(|i| i as f32 * 0.37).collect::<Vec<_>>();
+        assert!((mean(&data) - mean_parallel(&data)).abs() < 1e-2);
+    }
 }